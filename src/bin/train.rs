@@ -1,13 +1,14 @@
 use conllu::io::ReadSentence;
 use glob::glob;
-use std::cmp;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fs;
 use std::io::BufReader;
 use std::path::PathBuf;
 use udgraph::graph::Sentence;
 use udgraph::token::{Features, Token, Tokens};
+use vidyut::generator::{self, Case, Gender, Number, Person};
+use vidyut::to_slp1;
 
 /// Freq(`state[n]` | `state[n-1]`).
 ///
@@ -24,74 +25,11 @@ type Emissions = HashMap<String, HashMap<String, u32>>;
 /// Value of state_0 and any other tokens with unclear semantics.
 const INITIAL_STATE: &str = "START";
 
-/// Hackily transliterate from IAST to SLP1.
-fn to_slp1(input: &str) -> String {
-    let chars: Vec<char> = input.chars().collect();
-    let mut ret = String::new();
-    let mut i = 0;
-    while i < chars.len() {
-        let mut next: String = String::new();
-        let mut offset = 0;
+/// Add-k (Laplace) smoothing constant applied to transition and emission counts.
+const SMOOTHING_K: f64 = 1.0;
 
-        // Search for matches against our mapping. The longest IAST glyph has two characters,
-        // so search up to length 2. Start with 2 first so that we match greedily.
-        for j in [2, 1] {
-            let limit = cmp::min(i + j, chars.len());
-            let cur = String::from_iter(&chars[i..limit]);
-
-            offset = limit - i;
-            next = match cur.as_str() {
-                "ā" => "A",
-                "ī" => "I",
-                "ū" => "U",
-                "ṛ" => "f",
-                "ṝ" => "F",
-                "ḷ" => "x",
-                "ḹ" => "X",
-                "ai" => "E",
-                "au" => "O",
-                "ṃ" => "M",
-                "ḥ" => "H",
-                "ṅ" => "N",
-                "kh" => "K",
-                "gh" => "G",
-                "ch" => "C",
-                "jh" => "J",
-                "ñ" => "Y",
-                "ṭ" => "w",
-                "ṭh" => "W",
-                "ḍ" => "q",
-                "ḍh" => "Q",
-                "th" => "T",
-                "dh" => "D",
-                "ph" => "P",
-                "bh" => "B",
-                "ṇ" => "R",
-                "ś" => "S",
-                "ṣ" => "z",
-                "ḻ" => "L",
-                // It's tedious to use Some/None here, so just use the empty string if not found.
-                &_ => "",
-            }
-            .to_string();
-
-            // Found a match.
-            if !next.is_empty() {
-                break;
-            }
-        }
-
-        // No match found: use the previous character as-is.
-        if next.is_empty() {
-            next = String::from_iter(&chars[i..i + 1]);
-            offset = 1;
-        }
-
-        ret += &next;
-        i += offset;
-    }
-    ret
-}
+/// Emission/transition key that holds the mass reserved for out-of-vocabulary tokens and states.
+const UNKNOWN: &str = "<UNK>";
 
 /// Create a state label for the given nominal (noun, pronoun, adjective, numeral).
 ///
@@ -187,6 +125,66 @@ fn token_state(token: &Token) -> String {
     }
 }
 
+/// Maps the feature strings used in `nominal_state` to a generator gender.
+fn gender(features: &Features) -> Option<Gender> {
+    match features.get("Gender")?.as_str() {
+        "Masc" => Some(Gender::Masculine),
+        "Fem" => Some(Gender::Feminine),
+        "Neut" => Some(Gender::Neuter),
+        _ => None,
+    }
+}
+
+/// Maps the feature strings used in `nominal_state` to a generator case.
+fn case(features: &Features) -> Option<Case> {
+    match features.get("Case")?.as_str() {
+        "Nom" => Some(Case::Nominative),
+        "Acc" => Some(Case::Accusative),
+        "Ins" => Some(Case::Instrumental),
+        "Dat" => Some(Case::Dative),
+        "Abl" => Some(Case::Ablative),
+        "Gen" => Some(Case::Genitive),
+        "Loc" => Some(Case::Locative),
+        "Voc" => Some(Case::Vocative),
+        _ => None,
+    }
+}
+
+/// Maps the feature strings used in `nominal_state`/`tinanta_state` to a generator number.
+fn number(features: &Features) -> Option<Number> {
+    match features.get("Number")?.as_str() {
+        "Sing" => Some(Number::Singular),
+        "Dual" => Some(Number::Dual),
+        "Plur" => Some(Number::Plural),
+        _ => None,
+    }
+}
+
+/// Maps the feature strings used in `tinanta_state` to a generator person.
+fn person(features: &Features) -> Option<Person> {
+    match features.get("Person")?.as_str() {
+        "1" => Some(Person::First),
+        "2" => Some(Person::Second),
+        "3" => Some(Person::Third),
+        _ => None,
+    }
+}
+
+/// Generates the expected SLP1 surface form for a token, or `None` if we cannot.
+///
+/// `stem` is the token's lemma already transliterated to SLP1. We only synthesize forms for the
+/// nominal and verbal classes the generator supports; callers fall back to the lemma otherwise.
+fn surface_form(token: &Token, stem: &str) -> Option<String> {
+    let features = token.features();
+    match token.upos()? {
+        "NOUN" | "PRON" | "ADJ" | "PART" | "NUM" => {
+            generator::declension(stem, &gender(features)?, &case(features)?, &number(features)?)
+        }
+        "VERB" => generator::conjugation(stem, &person(features)?, &number(features)?),
+        _ => None,
+    }
+}
+
 fn process_sentence(sentence: Sentence, transitions: &mut Transitions, emissions: &mut Emissions) {
     let mut prev_state = INITIAL_STATE.to_string();
     for token in sentence.tokens() {
@@ -200,13 +198,16 @@ fn process_sentence(sentence: Sentence, transitions: &mut Transitions, emissions
             .or_insert(0);
         *c += 1;
 
-        // Freq(cur_token | cur_state )
+        // Freq(cur_form | cur_state )
         //
-        // The DCS data doesn't contain explicit forms, so make do with the lemma.
+        // The DCS data doesn't contain explicit forms, so synthesize the expected surface form
+        // from the lemma and the token's features, backing off to the lemma when we cannot.
+        let stem = to_slp1(&lemma);
+        let form = surface_form(token, &stem).unwrap_or(stem);
         let c = emissions
             .entry(cur_state.clone())
             .or_insert_with(HashMap::new)
-            .entry(to_slp1(&lemma))
+            .entry(form)
             .or_insert(0);
         *c += 1;
 
@@ -227,27 +228,60 @@ fn process_file(
     Ok(())
 }
 
-fn write_transitions(transitions: Transitions, path: &str) -> Result<(), Box<dyn Error>> {
+/// The set of all distinct states, which is the vocabulary the transitions smooth over.
+fn state_vocab(transitions: &Transitions) -> HashSet<String> {
+    let mut states: HashSet<String> = transitions.keys().cloned().collect();
+    for row in transitions.values() {
+        states.extend(row.keys().cloned());
+    }
+    states
+}
+
+/// The set of all distinct emitted forms, which is the vocabulary the emissions smooth over.
+fn emission_vocab(emissions: &Emissions) -> HashSet<String> {
+    emissions
+        .values()
+        .flat_map(|row| row.keys().cloned())
+        .collect()
+}
+
+/// Writes add-k smoothed transition probabilities plus a per-state `<UNK>` bucket.
+///
+/// With `P(cur | prev) = (count + k) / (n + k * V)` every unseen transition gets the same nonzero
+/// mass `k / (n + k * V)`, which we persist once per state under [`UNKNOWN`] so the decoder never
+/// sees a zero probability. `v` is the number of possible states (`V`).
+fn write_transitions(transitions: Transitions, k: f64, v: usize, path: &str) -> Result<(), Box<dyn Error>> {
     let mut w = csv::Writer::from_path(path)?;
     for (prev_state, counts) in transitions {
-        let n = counts.values().sum::<u32>();
+        let n = counts.values().sum::<u32>() as f64;
+        let denom = n + k * v as f64;
         for (cur_state, count) in counts {
-            let prob = (count as f64) / (n as f64);
+            let prob = (count as f64 + k) / denom;
             w.write_record(&[&prev_state, &cur_state, &prob.to_string()])?;
         }
+        let unknown = k / denom;
+        w.write_record(&[&prev_state, &UNKNOWN.to_string(), &unknown.to_string()])?;
         w.flush()?;
     }
     Ok(())
 }
 
-fn write_emissions(emissions: Emissions, path: &str) -> Result<(), Box<dyn Error>> {
+/// Writes add-k smoothed emission probabilities plus a per-state `<UNK>` bucket.
+///
+/// The `<UNK>` bucket reserves mass for out-of-vocabulary tokens; its size `k / (n + k * (v + 1))`
+/// shrinks as the state's own count `n` grows, via the shared denominator, where `v` is the emission
+/// vocabulary size and the `+ 1` accounts for the unknown bucket itself.
+fn write_emissions(emissions: Emissions, k: f64, v: usize, path: &str) -> Result<(), Box<dyn Error>> {
     let mut w = csv::Writer::from_path(path)?;
     for (state, counts) in emissions {
-        let n = counts.values().sum::<u32>();
+        let n = counts.values().sum::<u32>() as f64;
+        let denom = n + k * (v as f64 + 1.0);
         for (token, count) in counts {
-            let prob = (count as f64) / (n as f64);
+            let prob = (count as f64 + k) / denom;
             w.write_record(&[&state, &token, &prob.to_string()])?;
         }
+        let unknown = k / denom;
+        w.write_record(&[&state, &UNKNOWN.to_string(), &unknown.to_string()])?;
         w.flush()?;
     }
     Ok(())
@@ -264,8 +298,10 @@ fn process_files() -> Result<(), Box<dyn Error>> {
         process_file(path, &mut transitions, &mut emissions)?;
     }
 
-    write_transitions(transitions, "data/model/transitions.csv")?;
-    write_emissions(emissions, "data/model/emissions.csv")?;
+    let state_v = state_vocab(&transitions).len();
+    let emission_v = emission_vocab(&emissions).len();
+    write_transitions(transitions, SMOOTHING_K, state_v, "data/model/transitions.csv")?;
+    write_emissions(emissions, SMOOTHING_K, emission_v, "data/model/emissions.csv")?;
     Ok(())
 }
 
@@ -276,23 +312,3 @@ fn main() {
         std::process::exit(1);
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_to_slp1() {
-        assert_eq!(
-            to_slp1("a ā i ī u ū ṛ ṝ ḷ ḹ e ai o au ṃ ḥ"),
-            "a A i I u U f F x X e E o O M H"
-        );
-        assert_eq!(to_slp1("k kh g gh ṅ"), "k K g G N");
-        assert_eq!(to_slp1("c ch j jh ñ"), "c C j J Y");
-        assert_eq!(to_slp1("ṭ ṭh ḍ ḍh ṇ"), "w W q Q R");
-        assert_eq!(to_slp1("t th d dh n"), "t T d D n");
-        assert_eq!(to_slp1("p ph b bh m"), "p P b B m");
-        assert_eq!(to_slp1("y r l v"), "y r l v");
-        assert_eq!(to_slp1("ś ṣ s h ḻ"), "S z s h L");
-    }
-}
@@ -0,0 +1,200 @@
+//! Synthesizes inflected surface forms from a lemma and its morphology.
+//!
+//! The DCS data does not expose inflected forms, so the trainer backs off to lemmas and loses
+//! emission specificity. Given a stem plus the same features used to build a state label, this
+//! module applies declension and conjugation tables keyed on the stem's ending to reconstruct the
+//! expected SLP1 surface form. Unsupported stem classes return `None` so that callers can fall
+//! back to the lemma.
+
+pub enum Gender {
+    Masculine,
+    Feminine,
+    Neuter,
+}
+
+pub enum Case {
+    Nominative,
+    Accusative,
+    Instrumental,
+    Dative,
+    Ablative,
+    Genitive,
+    Locative,
+    Vocative,
+}
+
+pub enum Number {
+    Singular,
+    Dual,
+    Plural,
+}
+
+pub enum Person {
+    First,
+    Second,
+    Third,
+}
+
+/// Index into a `[singular, dual, plural]` ending triple.
+fn number_index(number: &Number) -> usize {
+    match number {
+        Number::Singular => 0,
+        Number::Dual => 1,
+        Number::Plural => 2,
+    }
+}
+
+/// Returns the declined SLP1 form of `stem`, or `None` if its class is unsupported.
+///
+/// We currently handle the thematic `a`-stems (masculine and neuter) and the feminine `A`-stems,
+/// which together cover the bulk of nominal tokens.
+pub fn declension(stem: &str, gender: &Gender, case: &Case, number: &Number) -> Option<String> {
+    let n = number_index(number);
+    match gender {
+        Gender::Masculine if stem.ends_with('a') => {
+            Some(base(stem, 1) + A_STEM_MASCULINE[case_index(case)][n])
+        }
+        Gender::Neuter if stem.ends_with('a') => {
+            Some(base(stem, 1) + A_STEM_NEUTER[case_index(case)][n])
+        }
+        Gender::Feminine if stem.ends_with('A') => {
+            Some(base(stem, 1) + LONG_A_STEM_FEMININE[case_index(case)][n])
+        }
+        _ => None,
+    }
+}
+
+/// Returns the conjugated SLP1 form of a thematic present stem, or `None` if unsupported.
+///
+/// Only the present-indicative parasmaipada of `a`-stems (e.g. `Bava` -> `Bavati`) is generated;
+/// anything else (bare roots, athematic stems) backs off to the lemma.
+pub fn conjugation(stem: &str, person: &Person, number: &Number) -> Option<String> {
+    if !stem.ends_with('a') {
+        return None;
+    }
+    let row = match person {
+        Person::First => &PRESENT_PARASMAIPADA[0],
+        Person::Second => &PRESENT_PARASMAIPADA[1],
+        Person::Third => &PRESENT_PARASMAIPADA[2],
+    };
+    Some(base(stem, 1) + row[number_index(number)])
+}
+
+/// Drops the last `n` characters from `stem`, leaving the inflectional base.
+fn base(stem: &str, n: usize) -> String {
+    let cut = stem.chars().count().saturating_sub(n);
+    stem.chars().take(cut).collect()
+}
+
+fn case_index(case: &Case) -> usize {
+    match case {
+        Case::Nominative => 0,
+        Case::Accusative => 1,
+        Case::Instrumental => 2,
+        Case::Dative => 3,
+        Case::Ablative => 4,
+        Case::Genitive => 5,
+        Case::Locative => 6,
+        Case::Vocative => 7,
+    }
+}
+
+// Endings are indexed `[case][number]` and appended to the base (stem minus its final vowel).
+
+const A_STEM_MASCULINE: [[&str; 3]; 8] = [
+    ["aH", "O", "AH"],       // Nominative
+    ["am", "O", "An"],       // Accusative
+    ["ena", "AByAm", "EH"],  // Instrumental
+    ["Aya", "AByAm", "eByaH"], // Dative
+    ["At", "AByAm", "eByaH"], // Ablative
+    ["asya", "ayoH", "AnAm"], // Genitive
+    ["e", "ayoH", "ezu"],    // Locative
+    ["a", "O", "AH"],        // Vocative
+];
+
+const A_STEM_NEUTER: [[&str; 3]; 8] = [
+    ["am", "e", "Ani"],      // Nominative
+    ["am", "e", "Ani"],      // Accusative
+    ["ena", "AByAm", "EH"],  // Instrumental
+    ["Aya", "AByAm", "eByaH"], // Dative
+    ["At", "AByAm", "eByaH"], // Ablative
+    ["asya", "ayoH", "AnAm"], // Genitive
+    ["e", "ayoH", "ezu"],    // Locative
+    ["a", "e", "Ani"],       // Vocative
+];
+
+const LONG_A_STEM_FEMININE: [[&str; 3]; 8] = [
+    ["A", "e", "AH"],        // Nominative
+    ["Am", "e", "AH"],       // Accusative
+    ["ayA", "AByAm", "ABiH"], // Instrumental
+    ["AyE", "AByAm", "AByaH"], // Dative
+    ["AyAH", "AByAm", "AByaH"], // Ablative
+    ["AyAH", "ayoH", "AnAm"], // Genitive
+    ["AyAm", "ayoH", "Asu"], // Locative
+    ["e", "e", "AH"],        // Vocative
+];
+
+// Rows are `[first, second, third]` person; columns are `[singular, dual, plural]` number.
+const PRESENT_PARASMAIPADA: [[&str; 3]; 3] = [
+    ["Ami", "AvaH", "AmaH"], // First
+    ["asi", "aTaH", "aTa"],  // Second
+    ["ati", "ataH", "anti"], // Third
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a_stem_masculine() {
+        assert_eq!(
+            declension("deva", &Gender::Masculine, &Case::Nominative, &Number::Singular),
+            Some("devaH".to_string())
+        );
+        assert_eq!(
+            declension("deva", &Gender::Masculine, &Case::Instrumental, &Number::Singular),
+            Some("devena".to_string())
+        );
+        assert_eq!(
+            declension("deva", &Gender::Masculine, &Case::Genitive, &Number::Plural),
+            Some("devAnAm".to_string())
+        );
+    }
+
+    #[test]
+    fn test_a_stem_neuter() {
+        assert_eq!(
+            declension("Pala", &Gender::Neuter, &Case::Nominative, &Number::Plural),
+            Some("PalAni".to_string())
+        );
+    }
+
+    #[test]
+    fn test_long_a_stem_feminine() {
+        assert_eq!(
+            declension("senA", &Gender::Feminine, &Case::Nominative, &Number::Singular),
+            Some("senA".to_string())
+        );
+        assert_eq!(
+            declension("senA", &Gender::Feminine, &Case::Instrumental, &Number::Plural),
+            Some("senABiH".to_string())
+        );
+    }
+
+    #[test]
+    fn test_conjugation() {
+        assert_eq!(
+            conjugation("Bava", &Person::Third, &Number::Singular),
+            Some("Bavati".to_string())
+        );
+        assert_eq!(conjugation("BU", &Person::Third, &Number::Singular), None);
+    }
+
+    #[test]
+    fn test_unsupported_stem() {
+        assert_eq!(
+            declension("rAjan", &Gender::Masculine, &Case::Nominative, &Number::Singular),
+            None
+        );
+    }
+}
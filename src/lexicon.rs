@@ -0,0 +1,67 @@
+/// An optional dictionary of recognized words and stems.
+///
+/// `sandhi::is_good_split` relies on hand-written phonotactic heuristics that both over- and
+/// under-accept. A `Lexicon` lets callers additionally require that a split's pieces are real
+/// forms: `first` must be a recognized complete form or known stem, and `second` must be able to
+/// begin one. The word list is compiled into a finite-state set (via the `fst` crate) so that both
+/// membership and prefix queries stay cheap at scale.
+use fst::automaton::Str;
+use fst::{Automaton, IntoStreamer, Set, Streamer};
+use std::error::Error;
+
+pub struct Lexicon {
+    set: Set<Vec<u8>>,
+}
+
+impl Lexicon {
+    /// Builds a lexicon from an iterator of words or stems.
+    ///
+    /// The input must be sorted lexicographically with no duplicates, as required by the
+    /// underlying FST builder. Load Hunspell-style or GRETIL-derived lists by sorting them first.
+    pub fn from_sorted_words<I, S>(words: I) -> Result<Lexicon, Box<dyn Error>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        let set = Set::from_iter(words)?;
+        Ok(Lexicon { set })
+    }
+
+    /// Returns whether `word` is a recognized complete form or stem.
+    pub fn contains(&self, word: &str) -> bool {
+        self.set.contains(word)
+    }
+
+    /// Returns whether any entry begins with `prefix`.
+    ///
+    /// Used to check that a split's `second` piece can begin a valid form.
+    pub fn starts_any(&self, prefix: &str) -> bool {
+        let matcher = Str::new(prefix).starts_with();
+        self.set.search(matcher).into_stream().next().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lexicon() -> Lexicon {
+        // Entries must be sorted.
+        Lexicon::from_sorted_words(vec!["gacCati", "rAma", "rAmayaRa"]).unwrap()
+    }
+
+    #[test]
+    fn test_contains() {
+        let lex = lexicon();
+        assert!(lex.contains("rAma"));
+        assert!(!lex.contains("rAm"));
+    }
+
+    #[test]
+    fn test_starts_any() {
+        let lex = lexicon();
+        assert!(lex.starts_any("rA"));
+        assert!(lex.starts_any("rAmay"));
+        assert!(!lex.starts_any("xy"));
+    }
+}
@@ -1,41 +1,78 @@
 /// Splits Sanskrit expressions according to a list of sandhi rules.
+use crate::lexicon::Lexicon;
+use aho_corasick::AhoCorasick;
 use multimap::MultiMap;
+use once_cell::sync::Lazy;
 use regex::Regex;
-use std::cmp;
 
 pub type SandhiMap = MultiMap<String, (String, String)>;
 
-/// Returns all possible splits for the given input.
-pub fn split(input: &str, rules: &SandhiMap) -> Vec<(String, String)> {
-    let mut res = Vec::new();
-    let len_longest_key = rules.keys().map(|x| x.len()).max().expect("Map is empty");
-    let len_input = input.len();
-
-    // When iterating, prefer making the first item as long as possible, as longer
-    // items are easier to rule out.
-    for i in (1..=len_input).rev() {
-        // Default: split as-is, no sandhi.
-        res.push((
-            String::from(&input[0..i]),
-            String::from(&input[i..len_input]),
-        ));
-
-        for j in i..cmp::min(len_input, i + len_longest_key + 1) {
-            let combination = &input[i..j];
-            // println!("{}-{} : {}", i, j, combination);
-            match rules.get_vec(combination) {
-                Some(pairs) => {
+/// Matches the rule left-hand sides against an input in a single left-to-right pass.
+///
+/// The rule keys are compiled once into an Aho-Corasick automaton (a trie over the LHS strings
+/// with failure links). Enumerating the matches that start at each position replaces the old
+/// nested `j` loop, which makes splitting long pada-chains practical.
+/// The output is identical to the previous implementation.
+pub struct Splitter {
+    rules: SandhiMap,
+    matcher: AhoCorasick,
+    keys: Vec<String>,
+}
+
+impl Splitter {
+    /// Compiles the given rules into a reusable splitter.
+    pub fn new(rules: SandhiMap) -> Splitter {
+        let keys: Vec<String> = rules.keys().cloned().collect();
+        assert!(!keys.is_empty(), "Map is empty");
+        let matcher = AhoCorasick::new(&keys).expect("Failed to build automaton");
+        Splitter {
+            rules,
+            matcher,
+            keys,
+        }
+    }
+
+    /// Returns all possible splits for the given input.
+    pub fn split(&self, input: &str) -> Vec<(String, String)> {
+        let len_input = input.len();
+
+        // Collect the rule matches (`combination`, `j`) that start at each offset `i` in one pass
+        // over the input, so that the loop below can enumerate them without re-scanning.
+        let mut matches_by_start: Vec<Vec<(&str, usize)>> = vec![Vec::new(); len_input + 1];
+        for m in self.matcher.find_overlapping_iter(input) {
+            // The old loop stopped at `len_input`, so a combination that consumes the final
+            // character was never formed. Preserve that to keep the output identical.
+            if m.end() < len_input {
+                matches_by_start[m.start()].push((self.keys[m.pattern()].as_str(), m.end()));
+            }
+        }
+        // Prefer shorter combinations first, mirroring the old ascending-`j` iteration.
+        for bucket in &mut matches_by_start {
+            bucket.sort_by_key(|&(_, j)| j);
+        }
+
+        let mut res = Vec::new();
+        // When iterating, prefer making the first item as long as possible, as longer
+        // items are easier to rule out.
+        for i in (1..=len_input).rev() {
+            // Default: split as-is, no sandhi.
+            res.push((
+                String::from(&input[0..i]),
+                String::from(&input[i..len_input]),
+            ));
+
+            for &(combination, j) in &matches_by_start[i] {
+                if let Some(pairs) = self.rules.get_vec(combination) {
                     for (f, s) in pairs {
                         let first = String::from(&input[0..i]) + f;
                         let second = String::from(s) + &input[j..len_input];
                         res.push((first, second))
                     }
                 }
-                None => continue,
             }
         }
+        res
     }
-    res
 }
 
 /// Returns whether the first item in a sandhi split is OK according to some basic heuristics.
@@ -47,21 +84,34 @@ fn is_good_first(text: &str) -> bool {
     }
 }
 
+/// Matches an initial yrlv followed by a sparsha, which is phonotactically invalid.
+static BAD_SECOND: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[yrlv][kKgGNcCjJYwWqQRtTdDnpPbBm]").unwrap());
+
 /// Returns whether the second item in a sandhi split is OK according to some basic heuristics.
 fn is_good_second(text: &str) -> bool {
     // Initial yrlv must not be followed by sparsha.
-    let r = Regex::new(r"^[yrlv][kKgGNcCjJYwWqQRtTdDnpPbBm]").unwrap();
-    !r.is_match(text)
+    !BAD_SECOND.is_match(text)
 }
 
 /// Returns whether a given sandhi split is OK according to some basic heuristics.
 ///
 /// Our sandhi splitting logic overgenerates, and some of its outputs are not phonetically valid.
 /// For most use cases, we recommend filtering the results of `split` with this function.
-pub fn is_good_split(text: &str, first: &str, second: &str) -> bool {
+///
+/// If `lexicon` is provided, we additionally require that `first` is a recognized form or stem and
+/// that `second` can begin a valid form. This tightens the crude phonotactic filters above.
+pub fn is_good_split(text: &str, first: &str, second: &str, lexicon: Option<&Lexicon>) -> bool {
     // To avoid recursion, require that `second` is not just a repeat of the inital state.
     let is_recursive = text == second;
-    is_good_first(first) && is_good_second(second) && !is_recursive
+    if !(is_good_first(first) && is_good_second(second) && !is_recursive) {
+        return false;
+    }
+    match lexicon {
+        // `second` may be empty, which terminates a segmentation and needs no dictionary check.
+        Some(lexicon) => lexicon.contains(first) && (second.is_empty() || lexicon.starts_any(second)),
+        None => true,
+    }
 }
 
 #[cfg(test)]
@@ -83,7 +133,8 @@ mod tests {
         .map(|&(f, s)| (f.to_string(), s.to_string()))
         .collect();
 
-        assert_eq!(split("ceti", &rules), expected);
+        let splitter = Splitter::new(rules);
+        assert_eq!(splitter.split("ceti"), expected);
     }
 
     #[test]
@@ -116,4 +167,15 @@ mod tests {
             assert!(!is_good_second(word));
         }
     }
+
+    #[test]
+    fn test_is_good_split_with_lexicon() {
+        let lexicon = Lexicon::from_sorted_words(vec!["ca", "iti"]).unwrap();
+        // `ca` is a known form and `iti` can begin a valid form.
+        assert!(is_good_split("caiti", "ca", "iti", Some(&lexicon)));
+        // `ce` is not in the lexicon.
+        assert!(!is_good_split("ceti", "ce", "ti", Some(&lexicon)));
+        // Without a lexicon, both pass the phonotactic checks.
+        assert!(is_good_split("ceti", "ce", "ti", None));
+    }
 }
@@ -0,0 +1,158 @@
+/// Finds the best full segmentation of a Sanskrit expression.
+///
+/// `sandhi::split` enumerates every possible split of its input, but it overgenerates and has no
+/// notion of which split is *best*. This module builds a word lattice by recursively splitting the
+/// right remainder, scores each candidate word with corpus frequencies, and recovers the
+/// highest-scoring complete path with a Viterbi pass over the lattice.
+use crate::lexicon::Lexicon;
+use crate::sandhi::{self, Splitter};
+use std::collections::HashMap;
+
+/// Freq(stem), used for a unigram score.
+pub type StemFrequency = HashMap<String, u32>;
+/// Freq(word[n-1], word[n]), used for a bigram score.
+pub type BigramFrequency = HashMap<(String, String), u32>;
+
+/// Memoizes the best suffix path keyed on `(remainder, previous word)`.
+type Memo = HashMap<(String, Option<String>), Option<(f64, Vec<String>)>>;
+
+/// Bonus added when a candidate word is a recognized stem.
+const DICT_BONUS: f64 = 2.0;
+
+/// Scores candidate words and word pairs against corpus frequencies.
+///
+/// The score combines a dictionary-membership bonus with an add-one smoothed unigram log-frequency
+/// and a bigram log-frequency between adjacent candidate words.
+pub struct Scorer {
+    stems: StemFrequency,
+    bigrams: BigramFrequency,
+    total_stems: u64,
+    total_bigrams: u64,
+    vocab: u64,
+}
+
+impl Scorer {
+    /// Creates a scorer from a stem-frequency table and a word-pair frequency table.
+    pub fn new(stems: StemFrequency, bigrams: BigramFrequency) -> Scorer {
+        let total_stems = stems.values().map(|&v| v as u64).sum();
+        let total_bigrams = bigrams.values().map(|&v| v as u64).sum();
+        let vocab = stems.len() as u64;
+        Scorer {
+            stems,
+            bigrams,
+            total_stems,
+            total_bigrams,
+            vocab,
+        }
+    }
+
+    /// Scores the edge that appends `word` after `prev` (`None` at the start of the path).
+    fn score_edge(&self, prev: Option<&str>, word: &str) -> f64 {
+        let count = self.stems.get(word).copied().unwrap_or(0);
+        let dict_bonus = if self.stems.contains_key(word) {
+            DICT_BONUS
+        } else {
+            0.0
+        };
+        // Add-one smoothed so that unseen words stay finite rather than `-inf`.
+        let unigram =
+            ((count as f64 + 1.0) / (self.total_stems as f64 + self.vocab as f64 + 1.0)).ln();
+        let bigram = match prev {
+            Some(prev) => {
+                let c = self
+                    .bigrams
+                    .get(&(prev.to_string(), word.to_string()))
+                    .copied()
+                    .unwrap_or(0);
+                ((c as f64 + 1.0) / (self.total_bigrams as f64 + self.vocab as f64 + 1.0)).ln()
+            }
+            None => 0.0,
+        };
+        dict_bonus + unigram + bigram
+    }
+}
+
+/// Returns the highest-scoring full segmentation of `input`, or `None` if none is possible.
+///
+/// We treat each distinct `(remainder, previous word)` pair as a lattice node and memoize the best
+/// suffix path from it, which is the Viterbi recurrence `best[node] = max(edge + best[next])` with
+/// the previous word carried as state so that bigram scores stay exact.
+pub fn segment(
+    input: &str,
+    splitter: &Splitter,
+    scorer: &Scorer,
+    lexicon: Option<&Lexicon>,
+) -> Option<Vec<String>> {
+    let mut memo: Memo = HashMap::new();
+    best_path(input, None, splitter, scorer, lexicon, &mut memo).map(|(_, words)| words)
+}
+
+fn best_path(
+    remainder: &str,
+    prev: Option<&str>,
+    splitter: &Splitter,
+    scorer: &Scorer,
+    lexicon: Option<&Lexicon>,
+    memo: &mut Memo,
+) -> Option<(f64, Vec<String>)> {
+    // A fully consumed input is a complete path of score 0.
+    if remainder.is_empty() {
+        return Some((0.0, Vec::new()));
+    }
+
+    let key = (remainder.to_string(), prev.map(String::from));
+    if let Some(cached) = memo.get(&key) {
+        return cached.clone();
+    }
+    // Guard against cycles while the recursive calls below are still in flight.
+    memo.insert(key.clone(), None);
+
+    let mut best: Option<(f64, Vec<String>)> = None;
+    for (first, second) in splitter.split(remainder) {
+        // Prune phonotactically invalid splits and the `text == second` recursion.
+        if !sandhi::is_good_split(remainder, &first, &second, lexicon) {
+            continue;
+        }
+        let edge = scorer.score_edge(prev, &first);
+        if let Some((rest_score, rest_words)) =
+            best_path(&second, Some(&first), splitter, scorer, lexicon, memo)
+        {
+            let score = edge + rest_score;
+            if best.as_ref().is_none_or(|(b, _)| score > *b) {
+                let mut words = Vec::with_capacity(rest_words.len() + 1);
+                words.push(first.clone());
+                words.extend(rest_words);
+                best = Some((score, words));
+            }
+        }
+    }
+
+    memo.insert(key, best.clone());
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sandhi::SandhiMap;
+
+    fn splitter() -> Splitter {
+        let mut rules = SandhiMap::new();
+        rules.insert("e".to_string(), ("a".to_string(), "i".to_string()));
+        Splitter::new(rules)
+    }
+
+    #[test]
+    fn test_segment_prefers_known_stems() {
+        let stems = StemFrequency::from([("ca".to_string(), 100), ("iti".to_string(), 100)]);
+        let scorer = Scorer::new(stems, BigramFrequency::new());
+        let seg = segment("ceti", &splitter(), &scorer, None).unwrap();
+        assert_eq!(seg, vec!["ca".to_string(), "iti".to_string()]);
+    }
+
+    #[test]
+    fn test_segment_always_returns_a_path() {
+        let scorer = Scorer::new(StemFrequency::new(), BigramFrequency::new());
+        assert!(segment("ceti", &splitter(), &scorer, None).is_some());
+    }
+}
@@ -0,0 +1,200 @@
+/// Tags Sanskrit text with an HMM trained by the `train` binary.
+///
+/// The trainer writes `transitions.csv` and `emissions.csv`. This module loads both back into
+/// probability maps and runs Viterbi decoding to recover the most likely sequence of states for a
+/// sequence of observed tokens.
+use crate::to_slp1;
+use std::collections::HashMap;
+use std::error::Error;
+
+/// `log P(state[n] | state[n-1])`, keyed first on `state[n-1]`.
+pub type Transitions = HashMap<String, HashMap<String, f64>>;
+/// `log P(token[n] | state[n])`, keyed first on `state[n]`.
+pub type Emissions = HashMap<String, HashMap<String, f64>>;
+
+/// Value of state_0, matching the trainer's initial state.
+const INITIAL_STATE: &str = "START";
+
+/// Key holding the mass reserved for out-of-vocabulary tokens and states, written by the trainer.
+const UNKNOWN: &str = "<UNK>";
+
+/// Log-probability for a state that has no `<UNK>` bucket at all (e.g. unseen `prev` state).
+///
+/// The trainer's add-k smoothing gives every known state an explicit `<UNK>` row, so this floor is
+/// only reached for states absent from the table entirely.
+const LOG_FLOOR: f64 = -46.0; // ~ ln(1e-20)
+
+/// A Viterbi decoder backed by trained transition and emission tables.
+pub struct Tagger {
+    transitions: Transitions,
+    emissions: Emissions,
+}
+
+impl Tagger {
+    /// Loads a tagger from the two CSVs written by the `train` binary.
+    pub fn from_csv(transitions_path: &str, emissions_path: &str) -> Result<Tagger, Box<dyn Error>> {
+        Ok(Tagger {
+            transitions: read_table(transitions_path)?,
+            emissions: read_table(emissions_path)?,
+        })
+    }
+
+    /// Returns `log P(cur | prev)`, backing off to the smoothed `<UNK>` bucket for unseen pairs.
+    fn transition(&self, prev: &str, cur: &str) -> f64 {
+        lookup(&self.transitions, prev, cur)
+    }
+
+    /// Returns `log P(obs | state)`, backing off to the smoothed `<UNK>` bucket for unseen forms.
+    fn emission(&self, state: &str, obs: &str) -> f64 {
+        lookup(&self.emissions, state, obs)
+    }
+
+    /// Tags `observations` with the most likely sequence of states.
+    ///
+    /// Observations are transliterated to SLP1 before lookup, matching the trainer. Returns the
+    /// best state path together with its total log-likelihood. An empty input yields an empty path
+    /// and a log-likelihood of 0.
+    pub fn tag(&self, observations: &[String]) -> (Vec<String>, f64) {
+        // The emitting states are exactly the keys of the emission table.
+        let states: Vec<&String> = self.emissions.keys().collect();
+        if observations.is_empty() || states.is_empty() {
+            return (Vec::new(), 0.0);
+        }
+
+        let obs: Vec<String> = observations.iter().map(|x| to_slp1(x)).collect();
+
+        // `delta[i][s]` = best log-probability of any path ending in state `s` at position `i`.
+        // `back[i][s]` = the predecessor state that achieves `delta[i][s]`.
+        let mut delta: Vec<Vec<f64>> = vec![vec![f64::NEG_INFINITY; states.len()]; obs.len()];
+        let mut back: Vec<Vec<usize>> = vec![vec![0; states.len()]; obs.len()];
+
+        // Base case: transition out of START into each state.
+        for (s, state) in states.iter().enumerate() {
+            delta[0][s] = self.transition(INITIAL_STATE, state) + self.emission(state, &obs[0]);
+        }
+
+        // Recurrence.
+        for i in 1..obs.len() {
+            for (s, state) in states.iter().enumerate() {
+                let emit = self.emission(state, &obs[i]);
+                for (p, prev) in states.iter().enumerate() {
+                    let score = delta[i - 1][p] + self.transition(prev, state);
+                    if score > delta[i][s] {
+                        delta[i][s] = score;
+                        back[i][s] = p;
+                    }
+                }
+                delta[i][s] += emit;
+            }
+        }
+
+        // Pick the best final state, then walk the backpointers to reconstruct the path.
+        let last = obs.len() - 1;
+        let (mut best, log_likelihood) = delta[last]
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|(s, score)| (s, *score))
+            .unwrap();
+
+        let mut path = vec![states[best].to_string(); obs.len()];
+        for i in (1..obs.len()).rev() {
+            best = back[i][best];
+            path[i - 1] = states[best].to_string();
+        }
+
+        (path, log_likelihood)
+    }
+}
+
+/// Looks up `log P(inner | key)`, backing off to the `<UNK>` bucket and then to the floor.
+fn lookup(table: &HashMap<String, HashMap<String, f64>>, key: &str, inner: &str) -> f64 {
+    match table.get(key) {
+        Some(row) => row
+            .get(inner)
+            .or_else(|| row.get(UNKNOWN))
+            .copied()
+            .unwrap_or(LOG_FLOOR),
+        None => LOG_FLOOR,
+    }
+}
+
+/// Reads a `from_state,to_key,prob` CSV into a nested map of log-probabilities.
+fn read_table(path: &str) -> Result<HashMap<String, HashMap<String, f64>>, Box<dyn Error>> {
+    let mut table: HashMap<String, HashMap<String, f64>> = HashMap::new();
+    let mut r = csv::ReaderBuilder::new().has_headers(false).from_path(path)?;
+    for record in r.records() {
+        let record = record?;
+        let key = record[0].to_string();
+        let inner = record[1].to_string();
+        let prob = record[2].parse::<f64>()?;
+        table
+            .entry(key)
+            .or_default()
+            .insert(inner, prob.ln());
+    }
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tagger() -> Tagger {
+        let mut transitions = Transitions::new();
+        transitions.insert(
+            INITIAL_STATE.to_string(),
+            HashMap::from([("n-m-1-s".to_string(), 0.6_f64.ln()), ("v-3-s".to_string(), 0.4_f64.ln())]),
+        );
+        transitions.insert(
+            "n-m-1-s".to_string(),
+            HashMap::from([("v-3-s".to_string(), 0.9_f64.ln()), ("n-m-1-s".to_string(), 0.1_f64.ln())]),
+        );
+        transitions.insert(
+            "v-3-s".to_string(),
+            HashMap::from([("n-m-1-s".to_string(), 0.5_f64.ln()), ("v-3-s".to_string(), 0.5_f64.ln())]),
+        );
+
+        let mut emissions = Emissions::new();
+        emissions.insert(
+            "n-m-1-s".to_string(),
+            HashMap::from([("rAmaH".to_string(), 0.8_f64.ln())]),
+        );
+        emissions.insert(
+            "v-3-s".to_string(),
+            HashMap::from([("gacCati".to_string(), 0.8_f64.ln())]),
+        );
+
+        Tagger { transitions, emissions }
+    }
+
+    #[test]
+    fn test_tag_prefers_expected_path() {
+        let t = tagger();
+        let (path, log_likelihood) =
+            t.tag(&["rāmaḥ".to_string(), "gacchati".to_string()]);
+        assert_eq!(path, vec!["n-m-1-s".to_string(), "v-3-s".to_string()]);
+        assert!(log_likelihood.is_finite());
+    }
+
+    #[test]
+    fn test_tag_empty() {
+        let t = tagger();
+        let (path, log_likelihood) = t.tag(&[]);
+        assert!(path.is_empty());
+        assert_eq!(log_likelihood, 0.0);
+    }
+
+    #[test]
+    fn test_emission_backs_off_to_unknown() {
+        let mut t = tagger();
+        t.emissions
+            .get_mut("v-3-s")
+            .unwrap()
+            .insert(UNKNOWN.to_string(), 0.01_f64.ln());
+        // An unseen form falls back to the state's `<UNK>` mass, not the floor.
+        assert_eq!(t.emission("v-3-s", "asti"), 0.01_f64.ln());
+        // A state with no `<UNK>` row still hits the floor.
+        assert_eq!(t.emission("n-m-1-s", "asti"), LOG_FLOOR);
+    }
+}